@@ -0,0 +1,275 @@
+// Assembler: the inverse of `VM::load`. Parses a textual source with the
+// 22 mnemonics, symbolic labels, and register names `r0..r7`, and emits the
+// same little-endian 16-bit stream `load` consumes.
+
+use std::fmt;
+
+use super::{BASE, NUM_REGISTERS};
+use super::disasm::{mnemonic, arity};
+
+#[derive(Debug)]
+pub struct AsmError {
+    pub line: usize,
+    pub col: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, col {}: {}", self.line, self.col, self.message)
+    }
+}
+
+fn err(line: usize, col: usize, message: String) -> AsmError {
+    AsmError { line, col, message }
+}
+
+/// One operand as written in source, before label resolution.
+enum Operand {
+    Register(u16),
+    Number(u16),
+    Label(String),
+}
+
+enum Item {
+    Instruction { op: u16, operands: Vec<Operand> },
+    Word(Operand),
+}
+
+struct Placed {
+    addr: usize,
+    item: Item,
+    line: usize,
+    col: usize,
+}
+
+fn mnemonic_opcode(word: &str) -> Option<u16> {
+    (0u16..22).find(|&op| mnemonic(op) == Some(word))
+}
+
+fn parse_register(word: &str) -> Option<u16> {
+    if word.len() == 2 && word.starts_with('r') {
+        word[1..].parse::<u16>().ok().filter(|&n| (n as usize) < NUM_REGISTERS)
+    } else {
+        None
+    }
+}
+
+/// Splits a line into a label (if any), a mnemonic/directive, and the rest
+/// of the line as raw operand text (comments stripped).
+fn tokenize_operands(text: &str) -> Vec<String> {
+    // Operands are comma-separated, except for the single-argument string
+    // literal form used by `.data`/`db`/`dw`, which is handled by the
+    // caller before this function is invoked.
+    text.split(',').map(|s| s.trim().to_owned()).filter(|s| !s.is_empty()).collect()
+}
+
+fn parse_operand(word: &str, line: usize, col: usize) -> Result<Operand, AsmError> {
+    if let Some(r) = parse_register(word) {
+        return Ok(Operand::Register(r));
+    }
+    if word.starts_with('\'') && word.ends_with('\'') && word.len() == 3 {
+        return Ok(Operand::Number(word.as_bytes()[1] as u16));
+    }
+    if let Ok(n) = word.parse::<u16>() {
+        return Ok(Operand::Number(n));
+    }
+    if word.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_') {
+        return Ok(Operand::Label(word.to_owned()));
+    }
+    Err(err(line, col, format!("invalid operand '{}'", word)))
+}
+
+/// Validates that register-only operand positions (mirroring
+/// `get_register`'s check) were actually given a register.
+fn check_register_operand(op: &Operand, mnemonic_name: &str, line: usize, col: usize) -> Result<(), AsmError> {
+    match *op {
+        Operand::Register(_) => Ok(()),
+        _ => Err(err(line, col, format!(
+            "first operand of {} must be a register (r0..r7)", mnemonic_name))),
+    }
+}
+
+/// Opcodes whose first operand is an lvalue that must be a register.
+fn first_operand_is_register(op: u16) -> bool {
+    matches!(op, 1 | 3 | 4 | 5 | 9 | 10 | 11 | 12 | 13 | 14 | 15 | 20)
+}
+
+fn parse_string_literal(text: &str, line: usize) -> Result<String, AsmError> {
+    let text = text.trim();
+    if text.len() >= 2 && text.starts_with('"') && text.ends_with('"') {
+        Ok(text[1..text.len() - 1].to_owned())
+    } else {
+        Err(err(line, 1, format!("expected a quoted string, got '{}'", text)))
+    }
+}
+
+/// Assembles `source` into the 16-bit word stream that `VM::load` expects.
+pub fn assemble(source: &str) -> Result<Vec<u16>, AsmError> {
+    let mut labels: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    let mut placed: Vec<Placed> = Vec::new();
+    let mut addr = 0usize;
+    // high-water mark of addresses actually occupied by a placed item, not
+    // just the layout cursor's final value -- a trailing `.org` that patches
+    // an earlier address must not shrink the image back down
+    let mut image_len = 0usize;
+
+    // Pass 1: lay out instructions/data, recording label addresses and
+    // leaving operand label references unresolved.
+    for (lineno, raw_line) in source.lines().enumerate() {
+        let line = lineno + 1;
+        let code = match raw_line.find(';') {
+            Some(i) => &raw_line[..i],
+            None => raw_line,
+        };
+        let mut code = code.trim();
+        if code.is_empty() {
+            continue;
+        }
+
+        // Label definitions: `foo:` at the start of the line.
+        if let Some(i) = code.find(':') {
+            let label = code[..i].trim().to_owned();
+            if !label.is_empty() && label.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                if labels.insert(label.clone(), addr).is_some() {
+                    return Err(err(line, 1, format!("duplicate label '{}'", label)));
+                }
+                code = code[i + 1..].trim();
+                if code.is_empty() {
+                    continue;
+                }
+            }
+        }
+
+        let mut parts = code.splitn(2, char::is_whitespace);
+        let head = parts.next().unwrap_or("").to_owned();
+        let rest = parts.next().unwrap_or("").trim();
+        let col = raw_line.find(&head).map(|i| i + 1).unwrap_or(1);
+
+        if head == ".org" {
+            addr = rest.parse::<usize>().map_err(|_| {
+                err(line, col, format!("invalid .org address '{}'", rest))
+            })?;
+            continue;
+        }
+
+        if head == ".data" || head == "db" || head == "dw" {
+            if rest.starts_with('"') {
+                let s = parse_string_literal(rest, line)?;
+                for c in s.chars() {
+                    placed.push(Placed { addr, item: Item::Word(Operand::Number(c as u16)), line, col });
+                    addr += 1;
+                    image_len = image_len.max(addr);
+                }
+            } else {
+                for word in tokenize_operands(rest) {
+                    let operand = parse_operand(&word, line, col)?;
+                    placed.push(Placed { addr, item: Item::Word(operand), line, col });
+                    addr += 1;
+                    image_len = image_len.max(addr);
+                }
+            }
+            continue;
+        }
+
+        let op = match mnemonic_opcode(&head) {
+            Some(op) => op,
+            None => return Err(err(line, col, format!("unknown mnemonic '{}'", head))),
+        };
+        let expected = arity(op).unwrap();
+        let words = tokenize_operands(rest);
+        if words.len() != expected {
+            return Err(err(line, col, format!(
+                "{} expects {} operand(s), got {}", head, expected, words.len())));
+        }
+        let mut operands = Vec::with_capacity(expected);
+        for (i, word) in words.iter().enumerate() {
+            let operand = parse_operand(word, line, col)?;
+            if i == 0 && first_operand_is_register(op) {
+                check_register_operand(&operand, &head, line, col)?;
+            }
+            operands.push(operand);
+        }
+        placed.push(Placed { addr, item: Item::Instruction { op, operands }, line, col });
+        addr += 1 + expected;
+        image_len = image_len.max(addr);
+    }
+
+    // Pass 2: resolve labels and emit the final word stream.
+    let mut image = vec![0u16; image_len];
+    let resolve = |operand: &Operand, line: usize, col: usize| -> Result<u16, AsmError> {
+        match *operand {
+            Operand::Register(r) => Ok(BASE + r),
+            Operand::Number(n) => Ok(n),
+            Operand::Label(ref name) => labels.get(name).map(|&a| a as u16).ok_or_else(|| {
+                err(line, col, format!("undefined label '{}'", name))
+            }),
+        }
+    };
+
+    for p in &placed {
+        match p.item {
+            Item::Word(ref operand) => {
+                image[p.addr] = resolve(operand, p.line, p.col)?;
+            }
+            Item::Instruction { op, ref operands } => {
+                image[p.addr] = op;
+                for (i, operand) in operands.iter().enumerate() {
+                    image[p.addr + 1 + i] = resolve(operand, p.line, p.col)?;
+                }
+            }
+        }
+    }
+
+    Ok(image)
+}
+
+/// Serializes an assembled image to the little-endian byte stream that
+/// `VM::load` reads back in.
+pub fn to_bytes(image: &[u16]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(image.len() * 2);
+    for &word in image {
+        bytes.push((word & 0xff) as u8);
+        bytes.push((word >> 8) as u8);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assembles_a_straight_line_program() {
+        let image = assemble("OUT 'H'\nOUT 10\nHALT\n").unwrap();
+        assert_eq!(image, vec![19, 72, 19, 10, 0]);
+    }
+
+    #[test]
+    fn resolves_forward_and_backward_labels() {
+        let image = assemble("JMP skip\nHALT\nskip:\n  OUT 'x'\n  HALT\n").unwrap();
+        assert_eq!(image[0], 6); // JMP
+        assert_eq!(image[1], 3); // resolved address of `skip`
+    }
+
+    #[test]
+    fn org_patching_an_earlier_address_does_not_shrink_the_image() {
+        // regression test: a trailing `.org` that rewinds the layout cursor
+        // to patch an earlier word must not leave the image sized by the
+        // cursor's final position instead of the highest address any item
+        // actually occupies.
+        let image = assemble(".org 0\nHALT\nHALT\nHALT\nHALT\n.org 1\nNOOP\n").unwrap();
+        assert_eq!(image.len(), 4);
+        assert_eq!(image[1], 21); // NOOP patched in over the second HALT
+    }
+
+    #[test]
+    fn rejects_unknown_mnemonics() {
+        assert!(assemble("FROBNICATE r0\n").is_err());
+    }
+
+    #[test]
+    fn rejects_non_register_lvalue() {
+        assert!(assemble("SET 5, 1\n").is_err());
+    }
+}
@@ -0,0 +1,219 @@
+// Interactive debugger front-end for `VM::step`. Supports address
+// breakpoints, single-stepping, and inspecting/patching memory, registers,
+// and the stack -- the tooling needed to reverse-engineer the challenge's
+// self-modifying routines without editing and recompiling.
+
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+use super::VM;
+use super::MEM_SIZE;
+use super::error::VmState;
+use super::disasm::{mnemonic, arity, format_operand};
+use super::snapshot;
+
+pub struct Debugger<'a> {
+    vm: &'a mut VM,
+    breakpoints: HashSet<usize>,
+    single_step: bool,
+}
+
+/// Decodes and formats just the instruction at `addr`, for the `disasm`
+/// command -- cheaper than a full recursive-descent pass every time the
+/// user wants to see where they are.
+fn format_instruction_at(memory: &[u16], addr: usize) -> String {
+    let op = memory[addr];
+    match arity(op) {
+        Some(n) if addr + n < memory.len() => {
+            let name = mnemonic(op).unwrap();
+            let as_char = name == "OUT" || name == "IN";
+            let operands: Vec<String> = memory[addr + 1..addr + 1 + n].iter()
+                .map(|&v| format_operand(v, as_char))
+                .collect();
+            if operands.is_empty() {
+                format!("{:04x}: {}", addr, name)
+            } else {
+                format!("{:04x}: {} {}", addr, name, operands.join(", "))
+            }
+        }
+        _ => format!("{:04x}: db {:#06x}", addr, op),
+    }
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(vm: &'a mut VM) -> Debugger<'a> {
+        // start paused so the user can set breakpoints before anything runs
+        Debugger { vm, breakpoints: HashSet::new(), single_step: true }
+    }
+
+    pub fn add_breakpoint(&mut self, addr: usize) {
+        self.breakpoints.insert(addr);
+    }
+
+    pub fn remove_breakpoint(&mut self, addr: usize) {
+        self.breakpoints.remove(&addr);
+    }
+
+    fn at_breakpoint(&self) -> bool {
+        self.breakpoints.contains(&self.vm.pc)
+    }
+
+    /// Reads one line of debugger command input from the terminal.
+    /// Returns `None` on end-of-input (e.g. the terminal closed).
+    fn read_command(&self) -> Option<String> {
+        print!("(dbg) ");
+        io::stdout().flush().ok();
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) => None,
+            _ => Some(line.trim().to_owned()),
+        }
+    }
+
+    /// Runs the read-eval loop. Drops to a prompt whenever `pc` hits a
+    /// breakpoint, or after every instruction while single-stepping.
+    /// Returns the VM's final state once it halts or faults.
+    pub fn run(&mut self) -> VmState {
+        loop {
+            if self.single_step || self.at_breakpoint() {
+                // `pc` can be left pointing past the end of memory (e.g. by
+                // a `set`/`feed`-induced fault, or a corrupted program) --
+                // `step()` will report that fault itself, so just skip the
+                // hint rather than indexing blindly.
+                if self.vm.pc < MEM_SIZE && mnemonic(self.vm.memory[self.vm.pc]) == Some("IN") {
+                    println!("(next instruction reads input at {:04x})", self.vm.pc);
+                }
+                match self.prompt() {
+                    PromptResult::Quit => return VmState::Halted,
+                    PromptResult::Step(n) => {
+                        self.single_step = true;
+                        for _ in 0..n - 1 {
+                            match self.vm.step() {
+                                VmState::Running => {}
+                                other => return other,
+                            }
+                        }
+                    }
+                    PromptResult::Continue => { self.single_step = false; }
+                }
+            }
+            match self.vm.step() {
+                VmState::Running => continue,
+                other => return other,
+            }
+        }
+    }
+
+    /// Shows the command prompt and applies commands until the user asks
+    /// to continue or single-step.
+    fn prompt(&mut self) -> PromptResult {
+        loop {
+            let line = match self.read_command() {
+                Some(line) => line,
+                None => return PromptResult::Quit,
+            };
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("disasm") | Some("d") => {
+                    if self.vm.pc < MEM_SIZE {
+                        println!("{}", format_instruction_at(&self.vm.memory, self.vm.pc));
+                    } else {
+                        println!("pc {:04x} is out of bounds", self.vm.pc);
+                    }
+                }
+                Some("regs") | Some("r") => {
+                    for (i, v) in self.vm.registers.iter().enumerate() {
+                        println!("r{} = {}", i, v);
+                    }
+                }
+                Some("stack") | Some("s") => {
+                    let n = words.next().and_then(|w| w.parse::<usize>().ok()).unwrap_or(self.vm.stack.len());
+                    let start = self.vm.stack.len().saturating_sub(n);
+                    for (i, v) in self.vm.stack[start..].iter().enumerate() {
+                        println!("[{}] = {}", start + i, v);
+                    }
+                }
+                Some("mem") | Some("m") => {
+                    match (words.next(), words.next()) {
+                        (Some(addr), None) => {
+                            match addr.parse::<usize>() {
+                                Ok(a) if a < self.vm.memory.len() => println!("{:04x}: {}", a, self.vm.memory[a]),
+                                _ => println!("bad address '{}'", addr),
+                            }
+                        }
+                        (Some(addr), Some(value)) => {
+                            match (addr.parse::<usize>(), value.parse::<u16>()) {
+                                (Ok(a), Ok(v)) if a < self.vm.memory.len() => self.vm.memory[a] = v,
+                                _ => println!("usage: mem <addr> [value]"),
+                            }
+                        }
+                        _ => println!("usage: mem <addr> [value]"),
+                    }
+                }
+                Some("set") => {
+                    match (words.next(), words.next()) {
+                        (Some(reg), Some(value)) if reg.starts_with('r') => {
+                            match (reg[1..].parse::<usize>(), value.parse::<u16>()) {
+                                (Ok(r), Ok(v)) if r < self.vm.registers.len() => self.vm.registers[r] = v,
+                                _ => println!("usage: set rN <value>"),
+                            }
+                        }
+                        _ => println!("usage: set rN <value>"),
+                    }
+                }
+                Some("feed") => {
+                    match words.next().and_then(|w| w.parse::<u8>().ok()) {
+                        Some(byte) => self.vm.feed_input(byte),
+                        None => println!("usage: feed <byte>"),
+                    }
+                }
+                Some("save") => {
+                    match words.next() {
+                        Some(path) => {
+                            if let Err(e) = snapshot::save_state(self.vm, path) {
+                                println!("save failed: {}", e);
+                            }
+                        }
+                        None => println!("usage: save <path>"),
+                    }
+                }
+                Some("load") => {
+                    match words.next() {
+                        Some(path) => {
+                            if let Err(e) = snapshot::load_state(self.vm, path) {
+                                println!("load failed: {}", e);
+                            }
+                        }
+                        None => println!("usage: load <path>"),
+                    }
+                }
+                Some("break") | Some("b") => {
+                    match words.next().and_then(|w| w.parse::<usize>().ok()) {
+                        Some(addr) => self.add_breakpoint(addr),
+                        None => println!("usage: break <addr>"),
+                    }
+                }
+                Some("clear") => {
+                    match words.next().and_then(|w| w.parse::<usize>().ok()) {
+                        Some(addr) => self.remove_breakpoint(addr),
+                        None => println!("usage: clear <addr>"),
+                    }
+                }
+                Some("continue") | Some("c") => return PromptResult::Continue,
+                Some("step") | Some("n") => {
+                    let n = words.next().and_then(|w| w.parse::<usize>().ok()).unwrap_or(1);
+                    return PromptResult::Step(n.max(1));
+                }
+                Some("quit") | Some("q") => return PromptResult::Quit,
+                Some(other) => println!("unknown command '{}'", other),
+                None => {}
+            }
+        }
+    }
+}
+
+enum PromptResult {
+    Continue,
+    Step(usize),
+    Quit,
+}
@@ -0,0 +1,189 @@
+// Disassembler: turns a raw memory image back into a readable instruction
+// listing. Two strategies are provided: a dumb linear sweep (decode every
+// address as if it were an instruction) and a recursive-descent sweep that
+// follows control flow so embedded strings/data don't get misread as code.
+
+use std::collections::{HashSet, VecDeque};
+
+use super::{BASE, NUM_REGISTERS};
+
+/// Mnemonic table indexed by opcode number.
+const MNEMONICS: [&str; 22] = [
+    "HALT", "SET", "PUSH", "POP", "EQ", "GT", "JMP", "JT", "JF", "ADD",
+    "MULT", "MOD", "AND", "OR", "NOT", "RMEM", "WMEM", "CALL", "RET", "OUT",
+    "IN", "NOOP",
+];
+
+/// Number of operand words each opcode consumes.
+const ARITY: [usize; 22] = [
+    0, 2, 1, 1, 3, 3, 1, 2, 2, 3,
+    3, 3, 3, 3, 2, 2, 2, 1, 0, 1,
+    1, 0,
+];
+
+pub fn mnemonic(op: u16) -> Option<&'static str> {
+    MNEMONICS.get(op as usize).cloned()
+}
+
+pub fn arity(op: u16) -> Option<usize> {
+    ARITY.get(op as usize).cloned()
+}
+
+fn is_printable_ascii(value: u16) -> bool {
+    value < 128 && {
+        let c = value as u8 as char;
+        c == ' ' || c.is_ascii_graphic()
+    }
+}
+
+/// Formats a raw operand word the way `get`/`get_register` would interpret
+/// it: `r0..r7` for register references, otherwise a decimal literal. When
+/// `as_char` is set (used for `OUT`/`IN` arguments) printable ASCII literals
+/// are rendered as a quoted character instead.
+pub(crate) fn format_operand(value: u16, as_char: bool) -> String {
+    if value >= BASE && (value - BASE) < NUM_REGISTERS as u16 {
+        format!("r{}", value - BASE)
+    } else if as_char && is_printable_ascii(value) {
+        format!("'{}'", value as u8 as char)
+    } else {
+        format!("{}", value)
+    }
+}
+
+/// One decoded instruction, or a data byte that wasn't reached as code.
+enum Decoded {
+    Instruction { op: u16, operands: Vec<u16> },
+    Data(u16),
+}
+
+fn format_line(addr: usize, decoded: &Decoded) -> String {
+    match *decoded {
+        Decoded::Instruction { op, ref operands } => {
+            let name = mnemonic(op).unwrap_or("???");
+            let as_char = name == "OUT" || name == "IN";
+            let operands: Vec<String> = operands.iter()
+                .map(|&v| format_operand(v, as_char))
+                .collect();
+            if operands.is_empty() {
+                format!("{:04x}: {}", addr, name)
+            } else {
+                format!("{:04x}: {:<4} {}", addr, name, operands.join(", "))
+            }
+        }
+        Decoded::Data(value) => {
+            format!("{:04x}: db {:#06x}", addr, value)
+        }
+    }
+}
+
+/// Decodes the instruction (if any) starting at `addr`, returning it along
+/// with the address immediately following it.
+fn decode_at(memory: &[u16], addr: usize) -> (Decoded, usize) {
+    let op = memory[addr];
+    match arity(op) {
+        Some(n) if addr + n < memory.len() => {
+            let operands = memory[addr + 1..addr + 1 + n].to_vec();
+            (Decoded::Instruction { op, operands }, addr + 1 + n)
+        }
+        _ => (Decoded::Data(op), addr + 1),
+    }
+}
+
+/// Linear-sweep disassembly: decodes every address in turn as an
+/// instruction, without regard for whether it's actually reachable code.
+/// Cheap and complete, but garbles embedded strings/data into nonsense
+/// instructions.
+pub fn disassemble_linear(memory: &[u16]) -> String {
+    let mut lines = Vec::new();
+    let mut addr = 0;
+    while addr < memory.len() {
+        let (decoded, next) = decode_at(memory, addr);
+        lines.push(format_line(addr, &decoded));
+        addr = next;
+    }
+    lines.join("\n")
+}
+
+/// Recursive-descent disassembly: starting from `entry`, follows
+/// `JMP`/`JT`/`JF`/`CALL` targets (and straight-line fallthrough) to find
+/// every address that's actually reachable as code. Anything never reached
+/// that way is emitted as a `db` directive instead of guessed-at code,
+/// which keeps embedded strings/data from being misread as instructions.
+pub fn disassemble(memory: &[u16], entry: usize) -> String {
+    let mut reached: HashSet<usize> = HashSet::new();
+    let mut worklist: VecDeque<usize> = VecDeque::new();
+    worklist.push_back(entry);
+
+    while let Some(addr) = worklist.pop_front() {
+        if addr >= memory.len() || reached.contains(&addr) {
+            continue;
+        }
+        let op = memory[addr];
+        let n = match arity(op) {
+            Some(n) if addr + n < memory.len() => n,
+            _ => continue, // not a valid instruction here; leave as data
+        };
+        for a in addr..=addr + n {
+            reached.insert(a);
+        }
+        let operands = &memory[addr + 1..addr + 1 + n];
+        let next = addr + 1 + n;
+        match mnemonic(op).unwrap_or("") {
+            "JMP" => worklist.push_back(operands[0] as usize),
+            "JT" | "JF" => {
+                worklist.push_back(next);
+                worklist.push_back(operands[1] as usize);
+            }
+            "CALL" => {
+                worklist.push_back(operands[0] as usize);
+                worklist.push_back(next);
+            }
+            "RET" | "HALT" => {} // no statically known successor
+            _ => worklist.push_back(next),
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut addr = 0;
+    while addr < memory.len() {
+        if reached.contains(&addr) {
+            let (decoded, next) = decode_at(memory, addr);
+            lines.push(format_line(addr, &decoded));
+            addr = next;
+        } else {
+            lines.push(format_line(addr, &Decoded::Data(memory[addr])));
+            addr += 1;
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_register_and_literal_operands() {
+        assert_eq!(format_operand(BASE + 2, false), "r2");
+        assert_eq!(format_operand(42, false), "42");
+        assert_eq!(format_operand(65, true), "'A'");
+    }
+
+    #[test]
+    fn linear_sweep_decodes_every_word_in_order() {
+        // OUT 'H'; HALT
+        let memory = [19, 72, 0];
+        let text = disassemble_linear(&memory);
+        assert_eq!(text, "0000: OUT  'H'\n0002: HALT");
+    }
+
+    #[test]
+    fn recursive_descent_treats_unreached_words_as_data() {
+        // JMP 3; db 99 (never reached); HALT
+        let memory = [6, 3, 99, 0];
+        let text = disassemble(&memory, 0);
+        assert!(text.contains("0000: JMP  3"));
+        assert!(text.contains("0002: db 0x0063"));
+        assert!(text.contains("0003: HALT"));
+    }
+}
@@ -0,0 +1,53 @@
+// Recoverable error type for VM execution faults, replacing the old
+// `fatal!` macro (print-then-panic) so the VM can be embedded as a library
+// that survives and reports faults instead of aborting the process.
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum VmError {
+    /// Opcode byte at `addr` isn't one of the 22 known instructions.
+    BadOpcode { op: u16, addr: usize },
+    /// A value read at `addr` is outside the valid 0..32775 range.
+    BadValue { value: u16, addr: usize },
+    /// An operand at `addr` was used as a register lvalue but doesn't
+    /// encode a register (32768..32775).
+    BadRegisterLValue { value: u16, addr: usize },
+    /// `POP`/`RET` was executed against an empty stack.
+    StackUnderflow { addr: usize },
+    /// `IN` failed to read a byte from the input source.
+    ReadError { addr: usize },
+}
+
+impl fmt::Display for VmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VmError::BadOpcode { op, addr } =>
+                write!(f, "bad opcode {} at address {}", op, addr),
+            VmError::BadValue { value, addr } =>
+                write!(f, "bad memory value: {} (#{}) at address {}", value, value as i32 - 0x8000, addr),
+            VmError::BadRegisterLValue { value, addr } =>
+                write!(f, "bad register lvalue: {} (#{}) at address {}", value, value as i32 - 0x8000, addr),
+            VmError::StackUnderflow { addr } =>
+                write!(f, "pop from empty stack at address {}", addr),
+            VmError::ReadError { addr } =>
+                write!(f, "read error at address {}", addr),
+        }
+    }
+}
+
+impl std::error::Error for VmError {}
+
+/// The outcome of running (or single-stepping) the VM.
+#[derive(Debug)]
+pub enum VmState {
+    /// The program is still executing; only returned by `step()`.
+    Running,
+    /// `HALT` was executed, or `RET` popped an empty stack.
+    Halted,
+    /// Execution hit a fault; the instruction that faulted is not applied.
+    Errored(VmError),
+    /// The configured instruction budget was reached before the program
+    /// halted, e.g. a runaway loop like the teleporter confirmation routine.
+    BudgetExceeded,
+}
@@ -1,8 +1,18 @@
+use std::collections::VecDeque;
 use std::env;
 use std::io;
 use std::io::Read;
+use std::io::Write;
 use std::fs::File;
 
+mod disasm;
+mod assemble;
+mod error;
+mod debugger;
+mod snapshot;
+
+use error::{VmError, VmState};
+
 const MEM_SIZE: usize = 0x8000;
 const NUM_REGISTERS: usize = 8;
 const BASE: u16 = 0x8000;
@@ -17,12 +27,24 @@ macro_rules! fatal {
 
 struct VM {
     // memory with 15-bit address space storing 16-bit values
-    memory: [u16; MEM_SIZE],
+    pub(crate) memory: [u16; MEM_SIZE],
     // eight registers
-    registers: [u16; NUM_REGISTERS],
+    pub(crate) registers: [u16; NUM_REGISTERS],
     // an unbounded stack which holds individual 16-bit values
-    stack: Vec<u16>,
-    pc: usize,
+    pub(crate) stack: Vec<u16>,
+    pub(crate) pc: usize,
+    // queued byte for the next `IN`, used by the debugger to feed input
+    // without going through the terminal
+    pub(crate) pending_input: Option<u8>,
+    // number of instructions executed so far
+    cycles: u64,
+    // optional instruction budget; `step()` stops once `cycles` reaches it
+    budget: Option<u64>,
+    // bytes queued for `IN` to replay a previously recorded input log,
+    // instead of reading the terminal
+    replay_queue: Option<VecDeque<u8>>,
+    // when set, every byte `IN` returns (from any source) is appended here
+    input_log: Option<Vec<u8>>,
 }
 
 impl VM {
@@ -32,8 +54,46 @@ impl VM {
             registers: [0; NUM_REGISTERS],
             stack: vec!(),
             pc: 0,
+            pending_input: None,
+            cycles: 0,
+            budget: None,
+            replay_queue: None,
+            input_log: None,
+        }
+    }
+
+    /// Starts recording every byte `IN` returns, so the session can be
+    /// replayed deterministically later with `set_replay`.
+    pub fn enable_input_log(&mut self) {
+        self.input_log = Some(Vec::new());
+    }
+
+    /// Writes the recorded input log (if logging is enabled) to `path`.
+    pub fn save_input_log(&self, path: &str) -> io::Result<()> {
+        match self.input_log {
+            Some(ref log) => File::create(path)?.write_all(log),
+            None => Ok(()),
         }
     }
+
+    /// Feeds `IN` from `log` instead of the terminal, one byte per call,
+    /// falling back to the terminal once the log is exhausted.
+    pub fn set_replay(&mut self, log: VecDeque<u8>) {
+        self.replay_queue = Some(log);
+    }
+
+    /// Caps execution to `limit` instructions; `step()`/`run()` return
+    /// `VmState::BudgetExceeded` instead of spinning forever once reached.
+    /// Useful for taming the challenge's notorious doubly-recursive
+    /// teleporter confirmation routine.
+    pub fn set_budget(&mut self, limit: u64) {
+        self.budget = Some(limit);
+    }
+
+    /// Number of instructions executed so far.
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
     
     pub fn init(&mut self, program: &[u16]) {
         for (i, v) in program.iter().enumerate() {
@@ -41,10 +101,13 @@ impl VM {
         }
     }
     
-    pub fn load(&mut self, filename: String) -> io::Result<()> {
+    /// Loads a program into memory starting at address 0. Returns the
+    /// number of words actually read, so callers (e.g. the disassembler)
+    /// can tell real program words from the zero-initialized rest of
+    /// memory.
+    pub fn load(&mut self, filename: String) -> io::Result<usize> {
         let mut file = try!(File::open(filename));
         let mut buffer = [0; 2];
-        // programs are loaded into memory starting at address 0
         let mut i = 0;
         loop {
             match file.read(&mut buffer) {
@@ -56,208 +119,454 @@ impl VM {
                 _ => { break; }
             };
         }
-        Ok(())
+        Ok(i)
     }
     
-    fn get(&mut self) -> u16 {
-        let a = self.memory[self.pc] as usize;
+    fn get(&mut self) -> Result<u16, VmError> {
+        let addr = self.pc;
+        let a = self.memory[addr] as usize;
         self.pc += 1;
         if a < MEM_SIZE {
             // numbers 0..32767 mean a literal value
-            a as u16
+            Ok(a as u16)
         } else if a - MEM_SIZE < NUM_REGISTERS {
             // numbers 32768..32775 instead mean registers 0..7
-            self.registers[a - MEM_SIZE]
+            Ok(self.registers[a - MEM_SIZE])
         } else {
             // numbers 32776..65535 are invalid
-            fatal!("bad memory value: {} (#{})\n", a, a - MEM_SIZE);
+            Err(VmError::BadValue { value: a as u16, addr })
         }
     }
-    
-    fn get_address(&mut self) -> usize {
-        self.get() as usize
+
+    /// Like `get`, but for operands used as a memory address (`JMP`/`JT`/
+    /// `JF`/`CALL`/`RMEM`/`WMEM`). A register can hold an arbitrary word
+    /// planted by `RMEM` from untrusted program data, so the resolved value
+    /// is re-validated against `MEM_SIZE` here rather than trusted as a
+    /// valid address just because `get` accepted it as a value.
+    fn get_address(&mut self) -> Result<usize, VmError> {
+        let addr = self.pc;
+        let v = self.get()?;
+        if (v as usize) < MEM_SIZE {
+            Ok(v as usize)
+        } else {
+            Err(VmError::BadValue { value: v, addr })
+        }
     }
-    
-    fn get_register(&mut self) -> usize {
-        let a = self.memory[self.pc] as usize;
+
+    fn get_register(&mut self) -> Result<usize, VmError> {
+        let addr = self.pc;
+        let a = self.memory[addr] as usize;
         self.pc += 1;
         if (MEM_SIZE <= a) && (a - MEM_SIZE < NUM_REGISTERS) {
-            a - MEM_SIZE
+            Ok(a - MEM_SIZE)
         } else {
-            fatal!("bad register lvalue: {} (#{})", a, a - MEM_SIZE);
+            Err(VmError::BadRegisterLValue { value: a as u16, addr })
         }
     }
     
-    pub fn run(&mut self) {
-        loop {
-            let op = self.memory[self.pc];
-            self.pc += 1;
-            match op {
-                0 => { // HALT
-                    // stop execution and terminate the program
-                    break;
-                }
-                1 => { // SET a b
-                    // set register <a> to the value of <b>
-                    let a = self.get_register();
-                    let b = self.get();
-                    self.registers[a] = b;
-                }
-                2 => { // PUSH a
-                    // push <a> onto the stack
-                    let a = self.get();
-                    self.stack.push(a);
-                }
-                3 => { // POP a
-                    // remove the top element from the stack and write it into <a>; empty stack = error
-                    match self.stack.pop() {
-                        Some(v) => {
-                            let a = self.get_register();
-                            self.registers[a] = v;
-                        }
-                        None => {
-                            fatal!("pop from empty stack at address {}", self.pc - 1);
-                        }
-                    }
-                }
-                4 => { // EQ a b c
-                    // set <a> to 1 if <b> is equal to <c>; set it to 0 otherwise
-                    let a = self.get_register();
-                    let b = self.get();
-                    let c = self.get();
-                    self.registers[a] = if b == c { 1 } else { 0 };
-                }
-                5 => { // GT a b c
-                    // set <a> to 1 if <b> is greater than <c>; set it to 0 otherwise
-                    let a = self.get_register();
-                    let b = self.get();
-                    let c = self.get();
-                    self.registers[a] = if b > c { 1 } else { 0 };
-                }
-                6 => { // JMP a
-                    // jump to <a>
-                    self.pc = self.get_address()
-                }
-                7 => { // JT a b
-                    // if <a> is nonzero, jump to <b>
-                    let a = self.get();
-                    let b = self.get_address();
-                    if a != 0 {
-                        self.pc = b;
+    /// Disassembles the first `len` words of the loaded program, following
+    /// control flow from `entry` so that embedded strings/data aren't
+    /// misread as code. `len` should be the word count `load` reported, so
+    /// the zero-initialized tail of memory isn't printed as a wall of
+    /// `db 0x0000` padding.
+    pub fn disassemble(&self, entry: usize, len: usize) -> String {
+        disasm::disassemble(&self.memory[..len], entry)
+    }
+
+    /// Executes a single instruction at the current `pc`. Returns `Ok(true)`
+    /// if it was `HALT` (or `RET` against an empty stack), `Ok(false)` if
+    /// execution should continue, or the `VmError` that faulted.
+    fn exec_one(&mut self) -> Result<bool, VmError> {
+        let op_addr = self.pc;
+        if op_addr >= MEM_SIZE {
+            // `pc` can land anywhere via `RET` popping an arbitrary stack
+            // value, so this can't be trusted as a valid index just because
+            // it's how we got here.
+            return Err(VmError::BadValue { value: op_addr as u16, addr: op_addr });
+        }
+        let op = self.memory[self.pc];
+        self.pc += 1;
+        match op {
+            0 => { // HALT
+                // stop execution and terminate the program
+                return Ok(true);
+            }
+            1 => { // SET a b
+                // set register <a> to the value of <b>
+                let a = self.get_register()?;
+                let b = self.get()?;
+                self.registers[a] = b;
+            }
+            2 => { // PUSH a
+                // push <a> onto the stack
+                let a = self.get()?;
+                self.stack.push(a);
+            }
+            3 => { // POP a
+                // remove the top element from the stack and write it into <a>; empty stack = error
+                match self.stack.pop() {
+                    Some(v) => {
+                        let a = self.get_register()?;
+                        self.registers[a] = v;
                     }
-                }
-                8 => { // JF a b
-                    // if <a> is zero, jump to <b>
-                    let a = self.get();
-                    let b = self.get_address();
-                    if a == 0 {
-                        self.pc = b;
+                    None => {
+                        return Err(VmError::StackUnderflow { addr: op_addr });
                     }
                 }
-                9 => { // ADD a b c
-                    // assign into <a> the sum of <b> and <c> (modulo 32768)
-                    let a = self.get_register();
-                    let b = self.get();
-                    let c = self.get();
-                    self.registers[a] = (b + c) % BASE;
-                }
-                10 => { // MULT a b c
-                    // store into <a> the product of <b> and <c> (modulo 32768)
-                    let a = self.get_register();
-                    let b = self.get();
-                    let c = self.get();
-                    self.registers[a] = b.wrapping_mul(c) % BASE;
-                }
-                11 => { // MOD a b c
-                    // store into <a> the remainder of <b> divided by <c>
-                    let a = self.get_register();
-                    let b = self.get();
-                    let c = self.get();
-                    self.registers[a] = b % c;
-                }
-                12 => { // AND a b c
-                    // stores into <a> the bitwise and of <b> and <c>
-                    let a = self.get_register();
-                    let b = self.get();
-                    let c = self.get();
-                    self.registers[a] = b & c;
-                }
-                13 => { // OR a b c
-                    // stores into <a> the bitwise or of <b> and <c>
-                    let a = self.get_register();
-                    let b = self.get();
-                    let c = self.get();
-                    self.registers[a] = b | c;
-                }
-                14 => { // NOT a b
-                    // stores 15-bit bitwise inverse of <b> in <a>
-                    let a = self.get_register();
-                    let b = self.get();
-                    self.registers[a] = !b & (BASE - 1);
-                }
-                15 => { // RMEM a b
-                    // read memory at address <b> and write it to <a>
-                    let a = self.get_register();
-                    let b = self.get_address();
-                    self.registers[a] = self.memory[b];
-                }
-                16 => { // WMEM a b
-                    // write the value from <b> into memory at address <a>
-                    let a = self.get_address();
-                    let b = self.get();
-                    self.memory[a] = b;
-                }
-                17 => { // CALL a
-                    // write the address of the next instruction to the stack and jump to <a>
-                    let a = self.get_address();
-                    self.stack.push(self.pc as u16);
-                    self.pc = a;
+            }
+            4 => { // EQ a b c
+                // set <a> to 1 if <b> is equal to <c>; set it to 0 otherwise
+                let a = self.get_register()?;
+                let b = self.get()?;
+                let c = self.get()?;
+                self.registers[a] = if b == c { 1 } else { 0 };
+            }
+            5 => { // GT a b c
+                // set <a> to 1 if <b> is greater than <c>; set it to 0 otherwise
+                let a = self.get_register()?;
+                let b = self.get()?;
+                let c = self.get()?;
+                self.registers[a] = if b > c { 1 } else { 0 };
+            }
+            6 => { // JMP a
+                // jump to <a>
+                self.pc = self.get_address()?;
+            }
+            7 => { // JT a b
+                // if <a> is nonzero, jump to <b>
+                let a = self.get()?;
+                let b = self.get_address()?;
+                if a != 0 {
+                    self.pc = b;
                 }
-                18 => { // RET
-                    // remove the top element from the stack and jump to it; empty stack = halt
-                    match self.stack.pop() {
-                        Some(v) => { self.pc = v as usize; }
-                        None    => { break; }
-                    }
+            }
+            8 => { // JF a b
+                // if <a> is zero, jump to <b>
+                let a = self.get()?;
+                let b = self.get_address()?;
+                if a == 0 {
+                    self.pc = b;
                 }
-                19 => { // OUT a
-                    // write the character represented by ascii code <a> to the terminal
-                    let a = self.get();
-                    print!("{}", a as u8 as char);
+            }
+            9 => { // ADD a b c
+                // assign into <a> the sum of <b> and <c> (modulo 32768)
+                let a = self.get_register()?;
+                let b = self.get()?;
+                let c = self.get()?;
+                self.registers[a] = (b + c) % BASE;
+            }
+            10 => { // MULT a b c
+                // store into <a> the product of <b> and <c> (modulo 32768)
+                let a = self.get_register()?;
+                let b = self.get()?;
+                let c = self.get()?;
+                self.registers[a] = b.wrapping_mul(c) % BASE;
+            }
+            11 => { // MOD a b c
+                // store into <a> the remainder of <b> divided by <c>
+                let a = self.get_register()?;
+                let b = self.get()?;
+                let c = self.get()?;
+                self.registers[a] = b % c;
+            }
+            12 => { // AND a b c
+                // stores into <a> the bitwise and of <b> and <c>
+                let a = self.get_register()?;
+                let b = self.get()?;
+                let c = self.get()?;
+                self.registers[a] = b & c;
+            }
+            13 => { // OR a b c
+                // stores into <a> the bitwise or of <b> and <c>
+                let a = self.get_register()?;
+                let b = self.get()?;
+                let c = self.get()?;
+                self.registers[a] = b | c;
+            }
+            14 => { // NOT a b
+                // stores 15-bit bitwise inverse of <b> in <a>
+                let a = self.get_register()?;
+                let b = self.get()?;
+                self.registers[a] = !b & (BASE - 1);
+            }
+            15 => { // RMEM a b
+                // read memory at address <b> and write it to <a>
+                let a = self.get_register()?;
+                let b = self.get_address()?;
+                self.registers[a] = self.memory[b];
+            }
+            16 => { // WMEM a b
+                // write the value from <b> into memory at address <a>
+                let a = self.get_address()?;
+                let b = self.get()?;
+                self.memory[a] = b;
+            }
+            17 => { // CALL a
+                // write the address of the next instruction to the stack and jump to <a>
+                let a = self.get_address()?;
+                self.stack.push(self.pc as u16);
+                self.pc = a;
+            }
+            18 => { // RET
+                // remove the top element from the stack and jump to it; empty stack = halt
+                match self.stack.pop() {
+                    Some(v) => { self.pc = v as usize; }
+                    None    => { return Ok(true); }
                 }
-                20 => { // IN a
-                    // read a character from the terminal and write its ascii code to <a>
-                    let a = self.get_register();
-                    match std::io::stdin().bytes().next() {
-                        Some(Ok(v)) => {
-                            let b = (v as u16) % BASE;
-                            self.registers[a] = b;
-                        }
-                        _ => {
-                            fatal!("read error");
+            }
+            19 => { // OUT a
+                // write the character represented by ascii code <a> to the terminal
+                let a = self.get()?;
+                print!("{}", a as u8 as char);
+            }
+            20 => { // IN a
+                // read a character from the terminal and write its ascii code to <a>
+                let a = self.get_register()?;
+                // a debugger can queue a byte ahead of time, or a replay
+                // log can supply recorded bytes, instead of this blocking
+                // on the terminal
+                let byte = match self.pending_input.take() {
+                    Some(v) => Some(Ok(v)),
+                    None => match self.replay_queue.as_mut().and_then(|q| q.pop_front()) {
+                        Some(v) => Some(Ok(v)),
+                        None => std::io::stdin().bytes().next(),
+                    },
+                };
+                match byte {
+                    Some(Ok(v)) => {
+                        if let Some(ref mut log) = self.input_log {
+                            log.push(v);
                         }
+                        let b = (v as u16) % BASE;
+                        self.registers[a] = b;
+                    }
+                    _ => {
+                        return Err(VmError::ReadError { addr: op_addr });
                     }
                 }
-                21 => { // NOOP
-                    // no operation
-                }
-                _ => {
-                    fatal!("bad opcode {} at address {}", op, self.pc - 1);
-                }
             }
+            21 => { // NOOP
+                // no operation
+            }
+            _ => {
+                return Err(VmError::BadOpcode { op, addr: op_addr });
+            }
+        }
+        Ok(false)
+    }
+
+    /// Runs until the program halts, faults, or exceeds its budget.
+    pub fn run(&mut self) -> VmState {
+        loop {
+            match self.step() {
+                VmState::Running => continue,
+                other => return other,
+            }
+        }
+    }
+
+    /// Executes exactly one instruction and returns control, so a debugger
+    /// can inspect/mutate state between instructions.
+    pub fn step(&mut self) -> VmState {
+        if let Some(limit) = self.budget {
+            if self.cycles >= limit {
+                return VmState::BudgetExceeded;
+            }
+        }
+        self.cycles += 1;
+        match self.exec_one() {
+            Ok(true) => VmState::Halted,
+            Ok(false) => VmState::Running,
+            Err(e) => VmState::Errored(e),
         }
     }
+
+    /// Queues a byte to be returned by the next `IN` instruction instead of
+    /// reading the terminal.
+    pub fn feed_input(&mut self, byte: u8) {
+        self.pending_input = Some(byte);
+    }
 }
 
 fn main() {
     let mut vm = VM::new();
     let program = vec![9, 32768, 32769, 65, 19, 32768];
     vm.init(&program);
-    let filename = env::args().nth(1).unwrap_or("challenge.bin".to_owned());
+
+    let mut args = env::args().skip(1);
+
+    // `--budget`/`--record` are accepted ahead of any mode (plain run,
+    // `--debug`, or `--replay`) so a debugging session can be budgeted or
+    // recorded just like a plain run.
+    let mut record_path: Option<String> = None;
+    let mut show_stats = false;
+    let mut first = args.next().unwrap_or("challenge.bin".to_owned());
+    while first == "--budget" || first == "--record" {
+        show_stats = true;
+        match first.as_str() {
+            "--budget" => {
+                let limit = args.next()
+                    .and_then(|w| w.parse::<u64>().ok())
+                    .unwrap_or_else(|| { fatal!("usage: --budget <n> ..."); });
+                vm.set_budget(limit);
+            }
+            "--record" => {
+                record_path = Some(args.next().unwrap_or_else(|| { fatal!("usage: --record <log-path> ..."); }));
+                vm.enable_input_log();
+            }
+            _ => unreachable!(),
+        }
+        first = args.next().unwrap_or("challenge.bin".to_owned());
+    }
+
+    if first == "--disassemble" || first == "--disassemble-linear" {
+        let linear = first == "--disassemble-linear";
+        let filename = args.next().unwrap_or("challenge.bin".to_owned());
+        let filename2 = filename.clone();
+        let len = match vm.load(filename) {
+            Ok(len) => len,
+            Err(_) => { fatal!("cannot read program file {}", filename2); }
+        };
+        if linear {
+            println!("{}", disasm::disassemble_linear(&vm.memory[..len]));
+        } else {
+            println!("{}", vm.disassemble(0, len));
+        }
+        return;
+    }
+
+    if first == "--assemble" {
+        let src_path = args.next().unwrap_or_else(|| { fatal!("usage: --assemble <source> <out.bin>"); });
+        let out_path = args.next().unwrap_or_else(|| { fatal!("usage: --assemble <source> <out.bin>"); });
+        let mut source = String::new();
+        match File::open(&src_path).and_then(|mut f| f.read_to_string(&mut source)) {
+            Ok(_) => {}
+            Err(_) => { fatal!("cannot read source file {}", src_path); }
+        }
+        match assemble::assemble(&source) {
+            Ok(image) => {
+                let bytes = assemble::to_bytes(&image);
+                match File::create(&out_path).and_then(|mut f| f.write_all(&bytes)) {
+                    Ok(_) => {}
+                    Err(_) => { fatal!("cannot write output file {}", out_path); }
+                }
+            }
+            Err(e) => { fatal!("assembly failed: {}", e); }
+        }
+        return;
+    }
+
+    if first == "--debug" {
+        let filename = args.next().unwrap_or("challenge.bin".to_owned());
+        let filename2 = filename.clone();
+        match vm.load(filename) {
+            Ok(_) => {}
+            Err(_) => { fatal!("cannot read program file {}", filename2); }
+        }
+        let mut dbg = debugger::Debugger::new(&mut vm);
+        let state = dbg.run();
+        if show_stats {
+            println!("\n({} instructions executed)", vm.cycles());
+        }
+        if let Some(path) = record_path {
+            if let Err(e) = vm.save_input_log(&path) {
+                println!("could not write input log {}: {}", path, e);
+            }
+        }
+        report_final_state(state);
+        return;
+    }
+
+    if first == "--replay" {
+        let snapshot_path = args.next().unwrap_or_else(|| { fatal!("usage: --replay <snapshot> <input-log>"); });
+        let log_path = args.next().unwrap_or_else(|| { fatal!("usage: --replay <snapshot> <input-log>"); });
+        match snapshot::load_state(&mut vm, &snapshot_path) {
+            Ok(_) => {}
+            Err(e) => { fatal!("cannot load snapshot {}: {}", snapshot_path, e); }
+        }
+        match snapshot::load_input_log(&log_path) {
+            Ok(log) => vm.set_replay(log),
+            Err(e) => { fatal!("cannot load input log {}: {}", log_path, e); }
+        }
+        report_final_state(vm.run());
+        return;
+    }
+
+    let filename = first;
     let filename2 = filename.clone();
     match vm.load(filename) {
         Ok(_) => {}
         Err(_) => { fatal!("cannot read program file {}", filename2); }
     }
-    vm.run();
+    let state = vm.run();
+    if show_stats {
+        println!("\n({} instructions executed)", vm.cycles());
+    }
+    if let Some(path) = record_path {
+        if let Err(e) = vm.save_input_log(&path) {
+            println!("could not write input log {}: {}", path, e);
+        }
+    }
+    report_final_state(state);
+}
+
+/// Prints a VM's final state and exits with a nonzero status on failure.
+fn report_final_state(state: VmState) {
+    match state {
+        VmState::Halted => {}
+        VmState::BudgetExceeded => {
+            println!("\n*** instruction budget exceeded");
+            std::process::exit(1);
+        }
+        VmState::Errored(e) => {
+            println!("\n*** VM ERROR: {}", e);
+            std::process::exit(1);
+        }
+        VmState::Running => unreachable!("execution only returns once it has stopped"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_runs_until_halt() {
+        let mut vm = VM::new();
+        vm.init(&[19, 72, 0]); // OUT 'H'; HALT
+        assert!(matches!(vm.step(), VmState::Running));
+        assert!(matches!(vm.step(), VmState::Halted));
+    }
+
+    #[test]
+    fn step_reports_budget_exceeded_before_faulting() {
+        let mut vm = VM::new();
+        vm.init(&[21, 6]); // NOOP; JMP 0 (loops forever)
+        vm.set_budget(3);
+        assert!(matches!(vm.step(), VmState::Running)); // NOOP
+        assert!(matches!(vm.step(), VmState::Running)); // JMP 0
+        assert!(matches!(vm.step(), VmState::Running)); // NOOP again
+        assert!(matches!(vm.step(), VmState::BudgetExceeded));
+    }
+
+    #[test]
+    fn assembled_program_disassembles_back_to_the_same_instructions() {
+        let image = assemble::assemble("OUT 'H'\nOUT 'i'\nHALT\n").unwrap();
+        let text = disasm::disassemble_linear(&image);
+        assert!(text.contains("OUT  'H'"));
+        assert!(text.contains("OUT  'i'"));
+        assert!(text.contains("HALT"));
+    }
+
+    #[test]
+    fn jump_to_an_out_of_range_address_faults_instead_of_panicking() {
+        // RMEM r0, 10 loads a raw, unvalidated word into r0; JMP r0 then
+        // tries to send pc there. Regression test for a register holding a
+        // corrupted address (e.g. planted by RMEM from untrusted program
+        // data) that used to panic instead of returning VmState::Errored.
+        let mut vm = VM::new();
+        vm.init(&[15, 32768, 10, 6, 32768, 0, 0, 0, 0, 0, 50000]);
+        assert!(matches!(vm.step(), VmState::Running)); // RMEM r0, 10
+        assert!(matches!(vm.step(), VmState::Errored(VmError::BadValue { value: 50000, .. })));
+    }
 }